@@ -1,51 +1,163 @@
-//! An `async` wrapper for `ureq::Request` that runs all blocking IO on the
-//! `blocking` thread pool.
+//! An `async` wrapper for `ureq::Request` that runs all blocking IO on a
+//! pluggable blocking executor (the `blocking` thread pool by default).
 
 use bytes::Bytes;
-use futures_lite::{future::block_on, AsyncRead, AsyncReadExt};
+use futures_lite::{AsyncRead, AsyncReadExt, Stream};
 use std::io;
+use std::io::Read;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Chunk size used when pumping a request or response body between the
+/// blocking side and the async side.
+const CHUNK_SIZE: usize = 8 * 1024;
 
 pub trait SendToPool: 'static + Send {}
 impl<T> SendToPool for T where T: 'static + Send {}
 
+/// Strategy for offloading a synchronous closure off the async executor.
+///
+/// The default [`BlockingPool`] routes work through the global `blocking`
+/// thread pool, but callers can supply their own: tokio users can delegate to
+/// `tokio::task::spawn_blocking`, smol users to their own pool, and library
+/// authors can cap concurrency with a bounded custom pool.
+#[async_trait::async_trait]
+pub trait BlockingExecutor {
+    async fn execute_blocking<T, F>(f: F) -> T
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static;
+}
+
+/// The default [`BlockingExecutor`], backed by the `blocking` crate's global
+/// thread pool.
+pub struct BlockingPool;
+
+#[async_trait::async_trait]
+impl BlockingExecutor for BlockingPool {
+    async fn execute_blocking<T, F>(f: F) -> T
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        blocking::unblock(f).await
+    }
+}
+
 /// Extension trait that gives [`ureq::Request`] async wrappers for all methods
 /// that perform blocking IO.
+///
+/// Each method has a `_with` variant generic over a [`BlockingExecutor`]; the
+/// plain methods delegate to [`BlockingPool`].
 #[async_trait::async_trait]
 pub trait AsyncRequest {
     async fn call_async(self) -> Result<ureq::Response, ureq::Error>;
 
+    async fn call_async_with<E: BlockingExecutor>(self) -> Result<ureq::Response, ureq::Error>;
+
     async fn send_async(
         self,
         reader: impl AsyncRead + SendToPool + Unpin,
     ) -> Result<ureq::Response, ureq::Error>;
 
+    async fn send_async_with<E: BlockingExecutor>(
+        self,
+        reader: impl AsyncRead + SendToPool + Unpin,
+    ) -> Result<ureq::Response, ureq::Error>;
+
     #[cfg(feature = "json")]
     async fn send_json_async(
         self,
         data: impl serde::Serialize + SendToPool,
     ) -> Result<ureq::Response, ureq::Error>;
 
+    #[cfg(feature = "json")]
+    async fn send_json_async_with<E: BlockingExecutor>(
+        self,
+        data: impl serde::Serialize + SendToPool,
+    ) -> Result<ureq::Response, ureq::Error>;
+
     async fn send_bytes_async(self, bytes: Bytes) -> Result<ureq::Response, ureq::Error>;
 
+    async fn send_bytes_async_with<E: BlockingExecutor>(
+        self,
+        bytes: Bytes,
+    ) -> Result<ureq::Response, ureq::Error>;
+
     async fn send_string_async(self, data: String) -> Result<ureq::Response, ureq::Error>;
 
+    async fn send_string_async_with<E: BlockingExecutor>(
+        self,
+        data: String,
+    ) -> Result<ureq::Response, ureq::Error>;
+
     async fn send_form_async<S: AsRef<str> + SendToPool>(
         self,
         form: Vec<(S, S)>,
     ) -> Result<ureq::Response, ureq::Error>;
+
+    async fn send_form_async_with<E: BlockingExecutor, S: AsRef<str> + SendToPool>(
+        self,
+        form: Vec<(S, S)>,
+    ) -> Result<ureq::Response, ureq::Error>;
 }
 
 #[async_trait::async_trait]
 impl AsyncRequest for ureq::Request {
     async fn call_async(self) -> Result<ureq::Response, ureq::Error> {
-        blocking::unblock(move || self.call()).await
+        self.call_async_with::<BlockingPool>().await
+    }
+
+    async fn call_async_with<E: BlockingExecutor>(self) -> Result<ureq::Response, ureq::Error> {
+        E::execute_blocking(move || self.call()).await
     }
 
     async fn send_async(
         self,
-        reader: impl AsyncReadExt + SendToPool + Unpin,
+        reader: impl AsyncRead + SendToPool + Unpin,
     ) -> Result<ureq::Response, ureq::Error> {
-        blocking::unblock(move || self.send(ReadProxy { reader })).await
+        self.send_async_with::<BlockingPool>(reader).await
+    }
+
+    async fn send_async_with<E: BlockingExecutor>(
+        self,
+        mut reader: impl AsyncRead + SendToPool + Unpin,
+    ) -> Result<ureq::Response, ureq::Error> {
+        let (sender, receiver) = async_channel::bounded::<io::Result<Vec<u8>>>(1);
+
+        // Drive the user's `AsyncRead` on the async executor, feeding fixed-size
+        // chunks through the bounded channel. The bound provides backpressure,
+        // and because the reader runs here (not inside `block_on` on a pool
+        // thread) it cannot deadlock against the executor it depends on.
+        let pump = async move {
+            let mut buf = vec![0u8; CHUNK_SIZE];
+            loop {
+                match reader.read(&mut buf).await {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        if sender.send(Ok(buf[..n].to_vec())).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        let _ = sender.send(Err(e)).await;
+                        break;
+                    }
+                }
+            }
+        };
+
+        // The synchronous side handed to `ureq` blocks on the channel.
+        let send = E::execute_blocking(move || {
+            self.send(ReadProxy {
+                receiver,
+                chunk: Vec::new(),
+                pos: 0,
+            })
+        });
+
+        let (_, result) = futures_lite::future::zip(pump, send).await;
+        result
     }
 
     #[cfg(feature = "json")]
@@ -53,22 +165,51 @@ impl AsyncRequest for ureq::Request {
         self,
         data: impl serde::Serialize + SendToPool,
     ) -> Result<ureq::Response, ureq::Error> {
-        blocking::unblock(move || self.send_json(data)).await
+        self.send_json_async_with::<BlockingPool>(data).await
+    }
+
+    #[cfg(feature = "json")]
+    async fn send_json_async_with<E: BlockingExecutor>(
+        self,
+        data: impl serde::Serialize + SendToPool,
+    ) -> Result<ureq::Response, ureq::Error> {
+        E::execute_blocking(move || self.send_json(data)).await
     }
 
     async fn send_bytes_async(self, bytes: Bytes) -> Result<ureq::Response, ureq::Error> {
-        blocking::unblock(move || self.send_bytes(&bytes)).await
+        self.send_bytes_async_with::<BlockingPool>(bytes).await
+    }
+
+    async fn send_bytes_async_with<E: BlockingExecutor>(
+        self,
+        bytes: Bytes,
+    ) -> Result<ureq::Response, ureq::Error> {
+        E::execute_blocking(move || self.send_bytes(&bytes)).await
     }
 
     async fn send_string_async(self, data: String) -> Result<ureq::Response, ureq::Error> {
-        blocking::unblock(move || self.send_string(&data)).await
+        self.send_string_async_with::<BlockingPool>(data).await
+    }
+
+    async fn send_string_async_with<E: BlockingExecutor>(
+        self,
+        data: String,
+    ) -> Result<ureq::Response, ureq::Error> {
+        E::execute_blocking(move || self.send_string(&data)).await
     }
 
     async fn send_form_async<S: AsRef<str> + SendToPool>(
         self,
         form: Vec<(S, S)>,
     ) -> Result<ureq::Response, ureq::Error> {
-        blocking::unblock(move || {
+        self.send_form_async_with::<BlockingPool, S>(form).await
+    }
+
+    async fn send_form_async_with<E: BlockingExecutor, S: AsRef<str> + SendToPool>(
+        self,
+        form: Vec<(S, S)>,
+    ) -> Result<ureq::Response, ureq::Error> {
+        E::execute_blocking(move || {
             let form_refs: Vec<_> = form.iter().map(|(k, v)| (k.as_ref(), v.as_ref())).collect();
             self.send_form(&form_refs)
         })
@@ -76,12 +217,359 @@ impl AsyncRequest for ureq::Request {
     }
 }
 
-struct ReadProxy<R> {
-    reader: R,
+/// Extension trait that gives [`ureq::Response`] async wrappers for consuming
+/// the response body without blocking the executor.
+///
+/// The `_with` variants are generic over a [`BlockingExecutor`]; the plain
+/// decoders delegate to [`BlockingPool`].
+#[async_trait::async_trait]
+pub trait AsyncResponse {
+    /// Consume the response body as an [`AsyncRead`].
+    ///
+    /// The blocking [`ureq::Response::into_reader`] loop runs on the `blocking`
+    /// thread pool and feeds fixed-size chunks through a bounded channel, so the
+    /// body streams incrementally instead of being buffered up front.
+    fn into_async_reader(self) -> AsyncBodyReader;
+
+    async fn into_string_async(self) -> io::Result<String>;
+
+    async fn into_string_async_with<E: BlockingExecutor>(self) -> io::Result<String>;
+
+    async fn into_bytes_async(self) -> io::Result<Vec<u8>>;
+
+    async fn into_bytes_async_with<E: BlockingExecutor>(self) -> io::Result<Vec<u8>>;
+
+    #[cfg(feature = "json")]
+    async fn into_json_async<T: serde::de::DeserializeOwned + Send + 'static>(self) -> io::Result<T>;
+
+    #[cfg(feature = "json")]
+    async fn into_json_async_with<E: BlockingExecutor, T: serde::de::DeserializeOwned + Send + 'static>(
+        self,
+    ) -> io::Result<T>;
+}
+
+#[async_trait::async_trait]
+impl AsyncResponse for ureq::Response {
+    fn into_async_reader(self) -> AsyncBodyReader {
+        let (sender, receiver) = async_channel::bounded::<io::Result<Vec<u8>>>(1);
+        blocking::unblock(move || {
+            let mut reader = self.into_reader();
+            loop {
+                let mut chunk = vec![0u8; CHUNK_SIZE];
+                match reader.read(&mut chunk) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        chunk.truncate(n);
+                        if sender.send_blocking(Ok(chunk)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        let _ = sender.send_blocking(Err(e));
+                        break;
+                    }
+                }
+            }
+        })
+        .detach();
+        AsyncBodyReader {
+            receiver: Box::pin(receiver),
+            chunk: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    async fn into_string_async(self) -> io::Result<String> {
+        self.into_string_async_with::<BlockingPool>().await
+    }
+
+    async fn into_string_async_with<E: BlockingExecutor>(self) -> io::Result<String> {
+        E::execute_blocking(move || self.into_string()).await
+    }
+
+    async fn into_bytes_async(self) -> io::Result<Vec<u8>> {
+        self.into_bytes_async_with::<BlockingPool>().await
+    }
+
+    async fn into_bytes_async_with<E: BlockingExecutor>(self) -> io::Result<Vec<u8>> {
+        E::execute_blocking(move || {
+            let mut buf = Vec::new();
+            self.into_reader().read_to_end(&mut buf)?;
+            Ok(buf)
+        })
+        .await
+    }
+
+    #[cfg(feature = "json")]
+    async fn into_json_async<T: serde::de::DeserializeOwned + Send + 'static>(self) -> io::Result<T> {
+        self.into_json_async_with::<BlockingPool, T>().await
+    }
+
+    #[cfg(feature = "json")]
+    async fn into_json_async_with<E: BlockingExecutor, T: serde::de::DeserializeOwned + Send + 'static>(
+        self,
+    ) -> io::Result<T> {
+        E::execute_blocking(move || self.into_json::<T>()).await
+    }
+}
+
+/// An [`AsyncRead`] over a [`ureq::Response`] body, fed by the blocking thread
+/// pool. Produced by [`AsyncResponse::into_async_reader`].
+pub struct AsyncBodyReader {
+    // `async_channel::Receiver` is deliberately `!Unpin`; box it behind a
+    // `Pin<Box<_>>` so `AsyncBodyReader` itself is `Unpin` and satisfies the
+    // `impl AsyncRead + Unpin` contract.
+    receiver: Pin<Box<async_channel::Receiver<io::Result<Vec<u8>>>>>,
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl AsyncRead for AsyncBodyReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        if this.pos >= this.chunk.len() {
+            match this.receiver.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(chunk))) => {
+                    this.chunk = chunk;
+                    this.pos = 0;
+                }
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Err(e)),
+                Poll::Ready(None) => return Poll::Ready(Ok(0)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        let n = (this.chunk.len() - this.pos).min(buf.len());
+        buf[..n].copy_from_slice(&this.chunk[this.pos..this.pos + n]);
+        this.pos += n;
+        Poll::Ready(Ok(n))
+    }
 }
 
-impl<R: AsyncRead + Unpin> io::Read for ReadProxy<R> {
+/// Offload a batch of requests to the blocking executor and await them
+/// concurrently (join-all style), preserving input order.
+///
+/// Handy for fanning out many independent requests for one inbound request and
+/// collecting the results without hand-rolling the `join_all` index bookkeeping.
+pub async fn call_all_async(
+    requests: Vec<ureq::Request>,
+) -> Vec<Result<ureq::Response, ureq::Error>> {
+    call_all_async_with::<BlockingPool>(requests, None).await
+}
+
+/// Like [`call_all_async`], but generic over the [`BlockingExecutor`] and with
+/// an optional `concurrency` cap so the batch doesn't oversubscribe the pool.
+pub async fn call_all_async_with<E: BlockingExecutor>(
+    requests: Vec<ureq::Request>,
+    concurrency: Option<usize>,
+) -> Vec<Result<ureq::Response, ureq::Error>> {
+    let limit = match concurrency {
+        Some(limit) if limit > 0 => limit,
+        _ => usize::MAX,
+    };
+    let calls: Vec<_> = requests
+        .into_iter()
+        .map(|req| req.call_async_with::<E>())
+        .collect();
+    join_all_ordered(calls, limit).await
+}
+
+/// Drive `futures` concurrently, running at most `limit` at a time and
+/// collecting their outputs in the original input order.
+async fn join_all_ordered<F: std::future::Future>(futures: Vec<F>, limit: usize) -> Vec<F::Output> {
+    let total = futures.len();
+    let mut queued = futures.into_iter().enumerate();
+    let mut results: Vec<Option<F::Output>> = (0..total).map(|_| None).collect();
+    // In-flight futures tagged with their original index so results land in order.
+    let mut in_flight: Vec<(usize, Pin<Box<F>>)> = Vec::new();
+
+    futures_lite::future::poll_fn(move |cx| loop {
+        // Top the in-flight set up to the concurrency limit.
+        while in_flight.len() < limit {
+            match queued.next() {
+                Some((idx, fut)) => in_flight.push((idx, Box::pin(fut))),
+                None => break,
+            }
+        }
+
+        // Poll every in-flight future, retiring the ones that have finished.
+        let mut retired = false;
+        let mut i = 0;
+        while i < in_flight.len() {
+            match in_flight[i].1.as_mut().poll(cx) {
+                Poll::Ready(out) => {
+                    let (idx, _) = in_flight.swap_remove(i);
+                    results[idx] = Some(out);
+                    retired = true;
+                }
+                Poll::Pending => i += 1,
+            }
+        }
+
+        if results.iter().all(Option::is_some) {
+            let done = std::mem::take(&mut results)
+                .into_iter()
+                .map(Option::unwrap)
+                .collect();
+            return Poll::Ready(done);
+        }
+        // Retiring a future frees a slot for a queued one; start it in this same
+        // poll, since nothing else will wake the task to do it.
+        if !retired || queued.len() == 0 {
+            return Poll::Pending;
+        }
+    })
+    .await
+}
+
+/// The synchronous `io::Read` passed into `ureq::send`. It pulls chunks produced
+/// by the async pump off a bounded channel via a blocking `recv`, returning
+/// `Ok(0)` once the sender closes.
+struct ReadProxy {
+    receiver: async_channel::Receiver<io::Result<Vec<u8>>>,
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl io::Read for ReadProxy {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        block_on(self.reader.read(buf))
+        if self.pos >= self.chunk.len() {
+            match self.receiver.recv_blocking() {
+                Ok(Ok(chunk)) => {
+                    self.chunk = chunk;
+                    self.pos = 0;
+                }
+                Ok(Err(e)) => return Err(e),
+                Err(_) => return Ok(0),
+            }
+        }
+        let n = (self.chunk.len() - self.pos).min(buf.len());
+        buf[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_lite::future::block_on;
+    use futures_lite::AsyncReadExt;
+    use std::future::Future;
+    use std::io::Read;
+
+    /// A future that yields `Pending` for `remaining` polls before resolving. Used to
+    /// force out-of-order completion so ordering logic is actually exercised.
+    struct ReadyAfter {
+        remaining: usize,
+        value: usize,
+    }
+
+    impl Future for ReadyAfter {
+        type Output = usize;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<usize> {
+            let this = self.get_mut();
+            if this.remaining == 0 {
+                Poll::Ready(this.value)
+            } else {
+                this.remaining -= 1;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn join_all_ordered_preserves_input_order() {
+        // Later indices finish first, so a naive completion-order collector would
+        // scramble the results.
+        let futures: Vec<_> = (0..4)
+            .map(|i| ReadyAfter {
+                remaining: 4 - i,
+                value: i,
+            })
+            .collect();
+        let out = block_on(join_all_ordered(futures, 2));
+        assert_eq!(out, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn body_reader_streams_chunks_then_eof() {
+        let (sender, receiver) = async_channel::bounded::<io::Result<Vec<u8>>>(4);
+        sender.send_blocking(Ok(b"hello ".to_vec())).unwrap();
+        sender.send_blocking(Ok(b"world".to_vec())).unwrap();
+        drop(sender);
+
+        let mut reader = AsyncBodyReader {
+            receiver: Box::pin(receiver),
+            chunk: Vec::new(),
+            pos: 0,
+        };
+        let mut buf = Vec::new();
+        let n = block_on(reader.read_to_end(&mut buf)).unwrap();
+        assert_eq!(n, 11);
+        assert_eq!(buf, b"hello world");
+    }
+
+    #[test]
+    fn body_reader_surfaces_error_once_then_eof() {
+        let (sender, receiver) = async_channel::bounded::<io::Result<Vec<u8>>>(4);
+        sender.send_blocking(Ok(b"ok".to_vec())).unwrap();
+        sender
+            .send_blocking(Err(io::Error::other("boom")))
+            .unwrap();
+        drop(sender);
+
+        let mut reader = AsyncBodyReader {
+            receiver: Box::pin(receiver),
+            chunk: Vec::new(),
+            pos: 0,
+        };
+        let mut buf = [0u8; 8];
+        assert_eq!(block_on(reader.read(&mut buf)).unwrap(), 2);
+        let err = block_on(reader.read(&mut buf)).unwrap_err();
+        assert_eq!(err.to_string(), "boom");
+        // The error is surfaced exactly once; the closed channel then reads as EOF.
+        assert_eq!(block_on(reader.read(&mut buf)).unwrap(), 0);
+    }
+
+    #[test]
+    fn read_proxy_drains_channel_then_returns_eof() {
+        let (sender, receiver) = async_channel::bounded::<io::Result<Vec<u8>>>(4);
+        sender.send_blocking(Ok(b"up".to_vec())).unwrap();
+        sender.send_blocking(Ok(b"load".to_vec())).unwrap();
+        drop(sender);
+
+        let mut proxy = ReadProxy {
+            receiver,
+            chunk: Vec::new(),
+            pos: 0,
+        };
+        let mut buf = Vec::new();
+        proxy.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, b"upload");
+        // Sender closed: further reads report EOF.
+        assert_eq!(proxy.read(&mut [0u8; 4]).unwrap(), 0);
+    }
+
+    #[test]
+    fn read_proxy_surfaces_error() {
+        let (sender, receiver) = async_channel::bounded::<io::Result<Vec<u8>>>(4);
+        sender
+            .send_blocking(Err(io::Error::other("nope")))
+            .unwrap();
+        drop(sender);
+
+        let mut proxy = ReadProxy {
+            receiver,
+            chunk: Vec::new(),
+            pos: 0,
+        };
+        let err = proxy.read(&mut [0u8; 4]).unwrap_err();
+        assert_eq!(err.to_string(), "nope");
     }
 }